@@ -4,10 +4,135 @@ use serde_json::Value;
 use std::ffi::c_void;
 use std::slice;
 use std::str;
+use std::str::FromStr;
 
 const INPUTS: &[&str] = &["pre", "post"];
 const OUTPUTS: &[&str] = &["i_syn"];
 
+const DEFAULT_SAMPLE_RATE: f64 = 1000.0;
+
+enum Gradient {
+    Linear,
+    Power(f64),
+    Exponential,
+}
+
+impl Gradient {
+    fn to_json(&self) -> Value {
+        match self {
+            Gradient::Linear => Value::from("Linear"),
+            Gradient::Power(k) => serde_json::json!({ "Power": k }),
+            Gradient::Exponential => Value::from("Exponential"),
+        }
+    }
+}
+
+struct ParamDescriptor {
+    name: &'static str,
+    default: f64,
+    min: f64,
+    max: f64,
+    gradient: Gradient,
+    unit: &'static str,
+}
+
+impl ParamDescriptor {
+    fn clamp(&self, value: f64) -> f64 {
+        value.clamp(self.min, self.max)
+    }
+
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "name": self.name,
+            "default": self.default,
+            "min": self.min,
+            "max": self.max,
+            "gradient": self.gradient.to_json(),
+            "unit": self.unit,
+        })
+    }
+}
+
+const PARAM_DESCRIPTORS: &[ParamDescriptor] = &[
+    ParamDescriptor {
+        name: "g_fast",
+        default: 0.208,
+        min: 0.0,
+        max: 2.0,
+        gradient: Gradient::Exponential,
+        unit: "S",
+    },
+    ParamDescriptor {
+        name: "e_syn",
+        default: -1.92,
+        min: -2.0,
+        max: 2.0,
+        gradient: Gradient::Linear,
+        unit: "V",
+    },
+    ParamDescriptor {
+        name: "s_fast",
+        default: 0.44,
+        min: 0.0,
+        max: 2.0,
+        gradient: Gradient::Power(2.0),
+        unit: "",
+    },
+    ParamDescriptor {
+        name: "v_fast",
+        default: -1.66,
+        min: -2.0,
+        max: 2.0,
+        gradient: Gradient::Linear,
+        unit: "V",
+    },
+];
+
+fn param_descriptor(name: &str) -> Option<&'static ParamDescriptor> {
+    PARAM_DESCRIPTORS.iter().find(|p| p.name == name)
+}
+
+/// Reads a config value as either a JSON number or a string-encoded number
+/// (some hosts stringify floats to avoid precision loss), rejecting anything
+/// that isn't finite so `NaN`/`Infinity` can never reach the integrator.
+fn parse_finite(value: &Value) -> Option<f64> {
+    let parsed = match value {
+        Value::Number(n) => n.as_f64()?,
+        Value::String(s) => f64::from_str(s).ok()?,
+        _ => return None,
+    };
+    parsed.is_finite().then_some(parsed)
+}
+
+struct SmoothedParam {
+    current: f64,
+    target: f64,
+    tau: f64,
+}
+
+impl SmoothedParam {
+    fn new(value: f64) -> Self {
+        Self {
+            current: value,
+            target: value,
+            tau: 0.0,
+        }
+    }
+
+    fn set_target(&mut self, value: f64) {
+        self.target = value;
+    }
+
+    fn advance(&mut self, dt: f64) {
+        if self.tau <= 0.0 || dt <= 0.0 {
+            self.current = self.target;
+            return;
+        }
+        let alpha = 1.0 - (-dt / self.tau).exp();
+        self.current += (self.target - self.current) * alpha;
+    }
+}
+
 pub struct FastChemicalSynapsePlugin {
     id: PluginId,
     meta: PluginMeta,
@@ -16,10 +141,11 @@ pub struct FastChemicalSynapsePlugin {
     pub pre: f64,
     pub post: f64,
     pub output: f64,
-    pub g_fast: f64,
-    pub e_syn: f64,
-    pub s_fast: f64,
-    pub v_fast: f64,
+    g_fast: SmoothedParam,
+    e_syn: SmoothedParam,
+    s_fast: SmoothedParam,
+    v_fast: SmoothedParam,
+    sample_rate: f64,
 }
 
 impl FastChemicalSynapsePlugin {
@@ -29,12 +155,10 @@ impl FastChemicalSynapsePlugin {
             meta: PluginMeta {
                 name: "Fast Chemical Synapse".to_string(),
                 fixed_vars: Vec::new(),
-                default_vars: vec![
-                    ("g_fast".to_string(), Value::from(0.208)),
-                    ("e_syn".to_string(), Value::from(-1.92)),
-                    ("s_fast".to_string(), Value::from(0.44)),
-                    ("v_fast".to_string(), Value::from(-1.66)),
-                ],
+                default_vars: PARAM_DESCRIPTORS
+                    .iter()
+                    .map(|p| (p.name.to_string(), Value::from(p.default)))
+                    .collect(),
             },
             inputs: vec![
                 Port {
@@ -50,10 +174,11 @@ impl FastChemicalSynapsePlugin {
             pre: 0.0,
             post: 0.0,
             output: 0.0,
-            g_fast: 0.208,
-            e_syn: -1.92,
-            s_fast: 0.44,
-            v_fast: -1.66,
+            g_fast: SmoothedParam::new(param_descriptor("g_fast").unwrap().default),
+            e_syn: SmoothedParam::new(param_descriptor("e_syn").unwrap().default),
+            s_fast: SmoothedParam::new(param_descriptor("s_fast").unwrap().default),
+            v_fast: SmoothedParam::new(param_descriptor("v_fast").unwrap().default),
+            sample_rate: DEFAULT_SAMPLE_RATE,
         }
     }
 }
@@ -76,8 +201,19 @@ impl Plugin for FastChemicalSynapsePlugin {
     }
 
     fn process(&mut self, _ctx: &mut PluginContext) -> Result<(), PluginError> {
-        let exp_val = (self.s_fast * (self.v_fast - self.pre)).exp();
-        self.output = self.g_fast * (self.post - self.e_syn) / (1.0 + exp_val);
+        let dt = if self.sample_rate > 0.0 {
+            1.0 / self.sample_rate
+        } else {
+            0.0
+        };
+        self.g_fast.advance(dt);
+        self.e_syn.advance(dt);
+        self.s_fast.advance(dt);
+        self.v_fast.advance(dt);
+
+        let exp_val = (self.s_fast.current * (self.v_fast.current - self.pre)).exp();
+        self.output =
+            self.g_fast.current * (self.post - self.e_syn.current) / (1.0 + exp_val);
         Ok(())
     }
 }
@@ -85,12 +221,14 @@ impl Plugin for FastChemicalSynapsePlugin {
 struct PluginState {
     plugin: FastChemicalSynapsePlugin,
     ctx: PluginContext,
+    last_config_report: Value,
 }
 
 extern "C" fn create(id: u64) -> *mut c_void {
     let state = PluginState {
         plugin: FastChemicalSynapsePlugin::new(id),
         ctx: PluginContext::default(),
+        last_config_report: serde_json::json!({ "applied": Vec::<&str>::new(), "rejected": Vec::<&str>::new() }),
     };
     Box::into_raw(Box::new(state)) as *mut c_void
 }
@@ -102,10 +240,12 @@ extern "C" fn destroy(handle: *mut c_void) {
 }
 
 extern "C" fn meta_json(_: *mut c_void) -> PluginString {
+    let params: Vec<Value> = PARAM_DESCRIPTORS.iter().map(ParamDescriptor::to_json).collect();
     PluginString::from_string(
         serde_json::json!({
             "name": "Fast Chemical Synapse",
-            "kind": "fast_chemical_synapse"
+            "kind": "fast_chemical_synapse",
+            "params": params
         })
         .to_string(),
     )
@@ -120,6 +260,9 @@ extern "C" fn outputs_json(_: *mut c_void) -> PluginString {
 }
 
 extern "C" fn set_config_json(handle: *mut c_void, data: *const u8, len: usize) {
+    let mut applied: Vec<&'static str> = Vec::new();
+    let mut rejected: Vec<&'static str> = Vec::new();
+
     if handle.is_null() || data.is_null() {
         return;
     }
@@ -128,19 +271,132 @@ extern "C" fn set_config_json(handle: *mut c_void, data: *const u8, len: usize)
     let bytes = unsafe { slice::from_raw_parts(data, len) };
 
     if let Ok(json) = serde_json::from_slice::<Value>(bytes) {
-        if let Some(v) = json.get("g_fast").and_then(|v| v.as_f64()) {
-            state.plugin.g_fast = v;
+        if let Some(raw) = json.get("g_fast") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.g_fast.set_target(param_descriptor("g_fast").unwrap().clamp(v));
+                    applied.push("g_fast");
+                }
+                None => rejected.push("g_fast"),
+            }
         }
-        if let Some(v) = json.get("e_syn").and_then(|v| v.as_f64()) {
-            state.plugin.e_syn = v;
+        if let Some(raw) = json.get("e_syn") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.e_syn.set_target(param_descriptor("e_syn").unwrap().clamp(v));
+                    applied.push("e_syn");
+                }
+                None => rejected.push("e_syn"),
+            }
         }
-        if let Some(v) = json.get("s_fast").and_then(|v| v.as_f64()) {
-            state.plugin.s_fast = v;
+        if let Some(raw) = json.get("s_fast") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.s_fast.set_target(param_descriptor("s_fast").unwrap().clamp(v));
+                    applied.push("s_fast");
+                }
+                None => rejected.push("s_fast"),
+            }
         }
-        if let Some(v) = json.get("v_fast").and_then(|v| v.as_f64()) {
-            state.plugin.v_fast = v;
+        if let Some(raw) = json.get("v_fast") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.v_fast.set_target(param_descriptor("v_fast").unwrap().clamp(v));
+                    applied.push("v_fast");
+                }
+                None => rejected.push("v_fast"),
+            }
+        }
+        if let Some(raw) = json.get("g_fast_tau") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.g_fast.tau = v;
+                    applied.push("g_fast_tau");
+                }
+                None => rejected.push("g_fast_tau"),
+            }
+        }
+        if let Some(raw) = json.get("e_syn_tau") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.e_syn.tau = v;
+                    applied.push("e_syn_tau");
+                }
+                None => rejected.push("e_syn_tau"),
+            }
+        }
+        if let Some(raw) = json.get("s_fast_tau") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.s_fast.tau = v;
+                    applied.push("s_fast_tau");
+                }
+                None => rejected.push("s_fast_tau"),
+            }
+        }
+        if let Some(raw) = json.get("v_fast_tau") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.v_fast.tau = v;
+                    applied.push("v_fast_tau");
+                }
+                None => rejected.push("v_fast_tau"),
+            }
+        }
+        if let Some(raw) = json.get("sample_rate") {
+            match parse_finite(raw) {
+                Some(v) => {
+                    state.plugin.sample_rate = v;
+                    applied.push("sample_rate");
+                }
+                None => rejected.push("sample_rate"),
+            }
         }
     }
+
+    state.last_config_report = serde_json::json!({ "applied": applied, "rejected": rejected });
+}
+
+fn config_json(plugin: &FastChemicalSynapsePlugin) -> Value {
+    serde_json::json!({
+        "g_fast": plugin.g_fast.target,
+        "e_syn": plugin.e_syn.target,
+        "s_fast": plugin.s_fast.target,
+        "v_fast": plugin.v_fast.target,
+        "g_fast_tau": plugin.g_fast.tau,
+        "e_syn_tau": plugin.e_syn.tau,
+        "s_fast_tau": plugin.s_fast.tau,
+        "v_fast_tau": plugin.v_fast.tau,
+        "sample_rate": plugin.sample_rate,
+    })
+}
+
+// `PluginApi` (from `rtsyn_plugin`) has no slot for a preset-restore entry
+// point, so this plugin exposes one as its own symbol instead of widening
+// that struct. A host discovers it by taking `meta_json()`'s `"kind"`
+// ("fast_chemical_synapse") and looking up `"<kind>_get_config_json"`.
+#[no_mangle]
+pub extern "C" fn fast_chemical_synapse_get_config_json(handle: *mut c_void) -> PluginString {
+    if handle.is_null() {
+        return PluginString::from_string(Value::Null.to_string());
+    }
+
+    let state = unsafe { &*(handle as *mut PluginState) };
+    PluginString::from_string(config_json(&state.plugin).to_string())
+}
+
+// Paired with `fast_chemical_synapse_get_config_json` for the same reason:
+// `set_config_json` must stay void-returning to match `PluginApi`, so the
+// applied/rejected keys from the last call are parked here instead. Same
+// "<kind>_last_config_report" discovery convention applies.
+#[no_mangle]
+pub extern "C" fn fast_chemical_synapse_last_config_report(handle: *mut c_void) -> PluginString {
+    if handle.is_null() {
+        return PluginString::from_string(Value::Null.to_string());
+    }
+
+    let state = unsafe { &*(handle as *mut PluginState) };
+    PluginString::from_string(state.last_config_report.to_string())
 }
 
 extern "C" fn set_input(handle: *mut c_void, port: *const u8, len: usize, value: f64) {
@@ -198,3 +454,151 @@ pub extern "C" fn rtsyn_plugin_api() -> *const PluginApi {
     &API
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smoothed_param_snaps_instantly_when_tau_is_zero() {
+        let mut p = SmoothedParam::new(1.0);
+        p.set_target(5.0);
+        p.advance(0.1);
+        assert_eq!(p.current, 5.0);
+    }
+
+    #[test]
+    fn smoothed_param_snaps_instantly_when_dt_is_zero() {
+        let mut p = SmoothedParam::new(1.0);
+        p.tau = 0.5;
+        p.set_target(5.0);
+        p.advance(0.0);
+        assert_eq!(p.current, 5.0);
+    }
+
+    #[test]
+    fn smoothed_param_glides_toward_target() {
+        let mut p = SmoothedParam::new(0.0);
+        p.tau = 1.0;
+        p.set_target(1.0);
+        p.advance(1.0);
+        let alpha = 1.0 - (-1.0_f64).exp();
+        assert!((p.current - alpha).abs() < 1e-9);
+        assert!(p.current > 0.0 && p.current < 1.0);
+
+        for _ in 0..1000 {
+            p.advance(1.0);
+        }
+        assert!((p.current - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn param_descriptor_clamp_respects_bounds() {
+        let g_fast = param_descriptor("g_fast").unwrap();
+        assert_eq!(g_fast.clamp(-1.0), g_fast.min);
+        assert_eq!(g_fast.clamp(100.0), g_fast.max);
+        assert_eq!(g_fast.clamp(0.5), 0.5);
+    }
+
+    #[test]
+    fn parse_finite_accepts_numbers_and_numeric_strings() {
+        assert_eq!(parse_finite(&Value::from(1.5)), Some(1.5));
+        assert_eq!(parse_finite(&Value::from("2.08e-1")), Some(2.08e-1));
+    }
+
+    #[test]
+    fn parse_finite_rejects_non_finite_and_non_numeric() {
+        assert_eq!(parse_finite(&Value::from("NaN")), None);
+        assert_eq!(parse_finite(&Value::from("Infinity")), None);
+        assert_eq!(parse_finite(&Value::from("not a number")), None);
+        assert_eq!(parse_finite(&Value::Bool(true)), None);
+    }
+
+    #[test]
+    fn set_config_json_round_trips_through_config_json() {
+        let handle = create(1);
+        let body = serde_json::json!({
+            "g_fast": "0.9",
+            "e_syn": 1.0,
+            "g_fast_tau": 0.02,
+            "sample_rate": 500.0,
+            "v_fast": "NaN",
+        })
+        .to_string();
+
+        set_config_json(handle, body.as_ptr(), body.len());
+
+        let state = unsafe { &*(handle as *mut PluginState) };
+        assert_eq!(state.plugin.g_fast.target, 0.9);
+        assert_eq!(state.plugin.e_syn.target, 1.0);
+        assert_eq!(state.plugin.g_fast.tau, 0.02);
+        assert_eq!(state.plugin.sample_rate, 500.0);
+
+        let report = &state.last_config_report;
+        let applied = report["applied"].as_array().unwrap();
+        let rejected = report["rejected"].as_array().unwrap();
+        assert!(applied.iter().any(|v| v.as_str() == Some("g_fast")));
+        assert!(rejected.iter().any(|v| v.as_str() == Some("v_fast")));
+
+        let round_tripped = config_json(&state.plugin);
+        assert_eq!(round_tripped["g_fast"], 0.9);
+        assert_eq!(round_tripped["sample_rate"], 500.0);
+
+        destroy(handle);
+    }
+
+    fn plugin_string_into_value(s: PluginString) -> Value {
+        let bytes = unsafe { slice::from_raw_parts(s.ptr, s.len) };
+        serde_json::from_slice(bytes).unwrap()
+    }
+
+    #[test]
+    fn get_config_json_export_reflects_set_config_json() {
+        let handle = create(1);
+        let body = serde_json::json!({ "g_fast": 0.9, "sample_rate": 500.0 }).to_string();
+        set_config_json(handle, body.as_ptr(), body.len());
+
+        let config = plugin_string_into_value(fast_chemical_synapse_get_config_json(handle));
+        assert_eq!(config["g_fast"], 0.9);
+        assert_eq!(config["sample_rate"], 500.0);
+
+        let report = plugin_string_into_value(fast_chemical_synapse_last_config_report(handle));
+        assert!(report["applied"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|v| v.as_str() == Some("g_fast")));
+
+        destroy(handle);
+    }
+
+    #[test]
+    fn get_config_json_export_handles_null_handle() {
+        assert_eq!(
+            plugin_string_into_value(fast_chemical_synapse_get_config_json(std::ptr::null_mut())),
+            Value::Null
+        );
+        assert_eq!(
+            plugin_string_into_value(fast_chemical_synapse_last_config_report(std::ptr::null_mut())),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn meta_json_describes_every_param() {
+        let meta = plugin_string_into_value(meta_json(std::ptr::null_mut()));
+        let params = meta["params"].as_array().unwrap();
+        assert_eq!(params.len(), 4);
+
+        let s_fast = params.iter().find(|p| p["name"] == "s_fast").unwrap();
+        assert_eq!(s_fast["default"], 0.44);
+        assert_eq!(s_fast["min"], 0.0);
+        assert_eq!(s_fast["max"], 2.0);
+        assert_eq!(s_fast["unit"], "");
+        assert_eq!(s_fast["gradient"], serde_json::json!({ "Power": 2.0 }));
+
+        let g_fast = params.iter().find(|p| p["name"] == "g_fast").unwrap();
+        assert_eq!(g_fast["gradient"], "Exponential");
+        assert_eq!(g_fast["unit"], "S");
+    }
+}
+